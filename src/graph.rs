@@ -0,0 +1,79 @@
+use nannou::prelude::*;
+
+// Graph in compressed sparse row layout. Edges have to be added grouped by
+// their source node in ascending order, which allows building it incrementally.
+pub struct Graph {
+    positions: Vec<Point2>,
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+    bounds: Option<Rect>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Graph {
+            positions: Vec::new(),
+            offsets: vec![0],
+            targets: Vec::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn edge_count(&self) -> u32 {
+        self.targets.len() as u32
+    }
+
+    pub fn node_count(&self) -> u32 {
+        self.positions.len() as u32
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = u32> {
+        0..self.node_count()
+    }
+
+    pub fn position(&self, node: u32) -> Point2 {
+        self.positions[node as usize]
+    }
+
+    pub fn bounds(&self) -> Option<Rect> {
+        self.bounds
+    }
+
+    pub fn neighbors(&self, node: u32) -> &[u32] {
+        let node = node as usize;
+        if node + 1 >= self.offsets.len() {
+            return &[];
+        }
+        &self.targets[self.offsets[node] as usize..self.offsets[node + 1] as usize]
+    }
+
+    // The source of the last added edge, edges with a smaller source can no longer be added.
+    pub fn last_source(&self) -> Option<u32> {
+        match self.offsets.len() {
+            1 => None,
+            len => Some(len as u32 - 2),
+        }
+    }
+
+    pub fn push_node(&mut self, pos: Point2) -> u32 {
+        let point = Rect::from_x_y_w_h(pos.x, pos.y, 0.0, 0.0);
+        self.bounds = Some(self.bounds.map_or(point, |bounds| bounds.stretch_to_point(pos)));
+        self.positions.push(pos);
+        self.node_count() - 1
+    }
+
+    pub fn push_edge(&mut self, from: u32, to: u32) {
+        debug_assert!(self.last_source().is_none_or(|last| from >= last), "edges must be sorted by source");
+        while self.offsets.len() < from as usize + 2 {
+            self.offsets.push(self.targets.len() as u32);
+        }
+        self.targets.push(to);
+        *self.offsets.last_mut().unwrap() = self.targets.len() as u32;
+    }
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Graph::new()
+    }
+}
@@ -0,0 +1,102 @@
+// string tables for all on-screen text
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Text {
+    StateIdle,
+    StateCalculating,
+    StateLoading,
+    LegendStart,
+    LegendGoal,
+    LegendVisited,
+    LegendPath,
+    HelpHint,
+    HelpTitle,
+    HelpLeftClick,
+    HelpRightClick,
+    HelpToggleHelp,
+    HelpSwitchLanguage,
+    HelpLanguage,
+    ErrorNoPath,
+    ErrorLoadIo,
+    ErrorLoadParse,
+    ErrorLoadUnknownNode,
+    ErrorLoadUnsortedEdges,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&l| l == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn tr(self, text: Text) -> &'static str {
+        match self {
+            Language::English => english(text),
+            Language::German => german(text),
+        }
+    }
+}
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::StateIdle => "Idle",
+        Text::StateCalculating => "Calculating shortest path...",
+        Text::StateLoading => "Loading graph",
+        Text::LegendStart => "Start",
+        Text::LegendGoal => "Goal",
+        Text::LegendVisited => "Visited",
+        Text::LegendPath => "Shortest path",
+        Text::HelpHint => "Press H for help",
+        Text::HelpTitle => "Controls",
+        Text::HelpLeftClick => "Left click: select start node",
+        Text::HelpRightClick => "Right click: select goal node",
+        Text::HelpToggleHelp => "H: show / hide help",
+        Text::HelpSwitchLanguage => "L: switch language",
+        Text::HelpLanguage => "Language:",
+        Text::ErrorNoPath => "No path between start and goal",
+        Text::ErrorLoadIo => "Failed to read graph file",
+        Text::ErrorLoadParse => "Invalid entry in graph file at line",
+        Text::ErrorLoadUnknownNode => "Edge references unknown node at line",
+        Text::ErrorLoadUnsortedEdges => "Edges are not sorted by source at line",
+    }
+}
+
+fn german(text: Text) -> &'static str {
+    match text {
+        Text::StateIdle => "Bereit",
+        Text::StateCalculating => "Berechne kürzesten Weg...",
+        Text::StateLoading => "Lade Graph",
+        Text::LegendStart => "Start",
+        Text::LegendGoal => "Ziel",
+        Text::LegendVisited => "Besucht",
+        Text::LegendPath => "Kürzester Weg",
+        Text::HelpHint => "H drücken für Hilfe",
+        Text::HelpTitle => "Steuerung",
+        Text::HelpLeftClick => "Linksklick: Startknoten wählen",
+        Text::HelpRightClick => "Rechtsklick: Zielknoten wählen",
+        Text::HelpToggleHelp => "H: Hilfe ein- / ausblenden",
+        Text::HelpSwitchLanguage => "L: Sprache wechseln",
+        Text::HelpLanguage => "Sprache:",
+        Text::ErrorNoPath => "Kein Weg zwischen Start und Ziel",
+        Text::ErrorLoadIo => "Graph-Datei konnte nicht gelesen werden",
+        Text::ErrorLoadParse => "Ungültiger Eintrag in der Graph-Datei in Zeile",
+        Text::ErrorLoadUnknownNode => "Kante verweist auf unbekannten Knoten in Zeile",
+        Text::ErrorLoadUnsortedEdges => "Kanten sind nicht nach Startknoten sortiert in Zeile",
+    }
+}
@@ -3,7 +3,10 @@ use wasm_bindgen::prelude::wasm_bindgen;
 
 use sketch::run_app;
 
-mod sketch;
+pub mod graph;
+pub mod i18n;
+pub mod loader;
+pub mod sketch;
 
 // web app entry_point
 #[wasm_bindgen]
@@ -12,6 +15,6 @@ pub async fn main_web() {
     console_error_panic_hook::set_once();
 
     block_on(async {
-        run_app().await;
+        run_app(None).await;
     });
 }
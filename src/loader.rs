@@ -0,0 +1,183 @@
+// Streams a graph file in chunks on a background thread.
+//
+// File format, one entry per line:
+//   v <x> <y>        node, ids are assigned in order of appearance starting at 0
+//   e <from> <to>    directed edge, grouped by <from> in ascending order
+//   # ...            comment
+// Nodes have to appear before the edges referencing them.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use nannou::prelude::*;
+
+use crate::graph::Graph;
+use crate::i18n::{Language, Text};
+
+const CHUNK_LINES: usize = 65_536;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse { line: usize },
+    UnknownNode { line: usize },
+    UnsortedEdges { line: usize },
+}
+
+impl LoadError {
+    pub fn message(&self, language: Language) -> String {
+        match self {
+            LoadError::Io(err) => format!("{}: {}", language.tr(Text::ErrorLoadIo), err),
+            LoadError::Parse { line } => format!("{} {}", language.tr(Text::ErrorLoadParse), line),
+            LoadError::UnknownNode { line } => format!("{} {}", language.tr(Text::ErrorLoadUnknownNode), line),
+            LoadError::UnsortedEdges { line } => format!("{} {}", language.tr(Text::ErrorLoadUnsortedEdges), line),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Chunk {
+    nodes: Vec<Point2>,
+    edges: Vec<(u32, u32)>,
+    bytes_read: u64,
+}
+
+impl Chunk {
+    pub fn apply(self, graph: &mut Graph) {
+        for pos in self.nodes {
+            graph.push_node(pos);
+        }
+        for (from, to) in self.edges {
+            graph.push_edge(from, to);
+        }
+    }
+}
+
+pub enum LoadStatus {
+    Loading,
+    Done,
+    Failed(LoadError),
+}
+
+pub struct Loader {
+    chunks: Receiver<Result<Chunk, LoadError>>,
+    bytes_total: u64,
+    bytes_read: u64,
+}
+
+impl Loader {
+    pub fn open(path: &str) -> Result<Self, LoadError> {
+        let file = File::open(path).map_err(LoadError::Io)?;
+        let bytes_total = file.metadata().map_err(LoadError::Io)?.len();
+        let (sender, chunks) = mpsc::sync_channel(4);
+        thread::spawn(move || {
+            let mut parser = Parser::default();
+            let mut reader = BufReader::new(file);
+            loop {
+                let mut chunk = Chunk::default();
+                let result = parser.read_chunk(&mut reader, &mut chunk);
+                let end_of_file = chunk.bytes_read == 0;
+                // Lines parsed before an error are still sent, so the loaded part stays usable.
+                if !end_of_file && sender.send(Ok(chunk)).is_err() {
+                    return;
+                }
+                if let Err(err) = result {
+                    let _ = sender.send(Err(err));
+                    return;
+                }
+                if end_of_file {
+                    return;
+                }
+            }
+        });
+        Ok(Loader { chunks, bytes_total, bytes_read: 0 })
+    }
+
+    pub fn progress(&self) -> f32 {
+        if self.bytes_total == 0 {
+            return 1.0;
+        }
+        self.bytes_read as f32 / self.bytes_total as f32
+    }
+
+    // Applies all chunks parsed so far without waiting for the next one.
+    pub fn poll(&mut self, graph: &mut Graph) -> LoadStatus {
+        loop {
+            match self.chunks.try_recv() {
+                Ok(Ok(chunk)) => {
+                    self.bytes_read += chunk.bytes_read;
+                    chunk.apply(graph);
+                }
+                Ok(Err(err)) => return LoadStatus::Failed(err),
+                Err(TryRecvError::Empty) => return LoadStatus::Loading,
+                Err(TryRecvError::Disconnected) => return LoadStatus::Done,
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    line: usize,
+    node_count: u32,
+    last_source: Option<u32>,
+}
+
+impl Parser {
+    // Fills the chunk with up to CHUNK_LINES lines, it stays empty once the end of the file is
+    // reached. On error the chunk keeps the lines parsed before the failing one.
+    fn read_chunk(&mut self, reader: &mut impl BufRead, chunk: &mut Chunk) -> Result<(), LoadError> {
+        let mut buffer = String::new();
+        for _ in 0..CHUNK_LINES {
+            buffer.clear();
+            let bytes = reader.read_line(&mut buffer).map_err(LoadError::Io)?;
+            if bytes == 0 {
+                break;
+            }
+            chunk.bytes_read += bytes as u64;
+            self.line += 1;
+            self.parse_line(buffer.trim(), chunk)?;
+        }
+        Ok(())
+    }
+
+    fn parse_line(&mut self, line: &str, chunk: &mut Chunk) -> Result<(), LoadError> {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            None => Ok(()),
+            Some(field) if field.starts_with('#') => Ok(()),
+            Some("v") => {
+                let x: f32 = self.field(fields.next())?;
+                let y: f32 = self.field(fields.next())?;
+                if !x.is_finite() || !y.is_finite() {
+                    return Err(LoadError::Parse { line: self.line });
+                }
+                chunk.nodes.push(Point2::new(x, y));
+                self.node_count += 1;
+                Ok(())
+            }
+            Some("e") => {
+                let from: u32 = self.field(fields.next())?;
+                let to: u32 = self.field(fields.next())?;
+                if from >= self.node_count || to >= self.node_count {
+                    return Err(LoadError::UnknownNode { line: self.line });
+                }
+                if self.last_source.is_some_and(|last| from < last) {
+                    return Err(LoadError::UnsortedEdges { line: self.line });
+                }
+                self.last_source = Some(from);
+                chunk.edges.push((from, to));
+                Ok(())
+            }
+            Some(_) => Err(LoadError::Parse { line: self.line }),
+        }
+    }
+
+    fn field<T: std::str::FromStr>(&self, field: Option<&str>) -> Result<T, LoadError> {
+        field
+            .and_then(|field| field.parse().ok())
+            .ok_or(LoadError::Parse { line: self.line })
+    }
+}
@@ -2,12 +2,10 @@
 
 use async_std::task::block_on;
 
-use sketch::run_app;
-
-mod sketch;
+use nannou_web_test::sketch::run_app;
 
 fn main() {
     block_on(async {
-        run_app().await;
+        run_app(std::env::args().nth(1)).await;
     });
 }
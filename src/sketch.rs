@@ -4,50 +4,83 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use nannou::prelude::*;
 use nannou::wgpu::{Backends, DeviceDescriptor, Limits};
 
+use crate::graph::Graph;
+use crate::i18n::{Language, Text};
+use crate::loader::{LoadError, LoadStatus, Loader};
+
 const WIDTH: u32 = 1000;
 const HEIGHT: u32 = 1000;
+// Fraction of the window the graph is fitted into, keeps nodes at the edges fully visible.
+const FIT: f32 = 0.9;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ModelState {
     Idle,
     CalculatingShortestPath,
 }
 
+#[derive(Clone, Copy)]
+struct ScreenTransform {
+    center: Point2,
+    scale: f32,
+}
+
+impl ScreenTransform {
+    fn apply(self, pos: Point2) -> Point2 {
+        (pos - self.center) * self.scale
+    }
+}
+
 pub struct Model {
-    graph: HashMap<u16, Vec<u16>>,
-    positions: HashMap<u16, Point2>,
-    left_clicked: u16,
-    right_clicked: u16,
-    predecessor: HashMap<u16, u16>,
-    shortest_path: Vec<u16>,
-    visited: HashSet<u16>,
-    queue: VecDeque<u16>,
+    graph: Graph,
+    loader: Option<Loader>,
+    load_error: Option<LoadError>,
+    left_clicked: u32,
+    right_clicked: u32,
+    predecessor: HashMap<u32, u32>,
+    shortest_path: Vec<u32>,
+    visited: HashSet<u32>,
+    queue: VecDeque<u32>,
     state: ModelState,
+    language: Language,
+    show_help: bool,
 }
 
 impl Model {
-    pub fn new_random(n: u16) -> Self {
-        let mut positions = HashMap::new();
-        for i in 0..n {
+    pub fn new_random(n: u32) -> Self {
+        let mut graph = Graph::new();
+        for _ in 0..n {
             let x = random_range(-(WIDTH as f32) / 2.0, WIDTH as f32 / 2.0);
             let y = random_range(-(HEIGHT as f32) / 2.0, HEIGHT as f32 / 2.0);
-            positions.insert(i, Point2::new(x, y));
+            graph.push_node(Point2::new(x, y));
         }
-        let mut graph = HashMap::new();
         for i in 0..n {
-            let mut close_neighbors = Vec::new();
             for j in (0..n).filter(|&j| j != i ) {
-                let distance = positions[&i].distance(positions[&j]);
+                let distance = graph.position(i).distance(graph.position(j));
                 if distance < WIDTH as f32 / 10.0 {
-                    close_neighbors.push(j);
+                    graph.push_edge(i, j);
                 }
-                
             }
-            graph.insert(i, close_neighbors);
         }
-        
+
+        Model::from_graph(graph)
+    }
+
+    // Starts streaming the graph from the file, the model can be used while loading.
+    pub fn load(path: &str) -> Self {
+        let mut model = Model::from_graph(Graph::new());
+        match Loader::open(path) {
+            Ok(loader) => model.loader = Some(loader),
+            Err(err) => model.load_error = Some(err),
+        }
+        model
+    }
+
+    pub fn from_graph(graph: Graph) -> Self {
         Model {
             graph,
-            positions,
+            loader: None,
+            load_error: None,
             left_clicked: 0,
             right_clicked: 1,
             predecessor: HashMap::new(),
@@ -55,6 +88,8 @@ impl Model {
             visited: HashSet::new(),
             queue: VecDeque::from([0]),
             state: ModelState::CalculatingShortestPath,
+            language: Language::default(),
+            show_help: false,
         }
     }
 
@@ -62,7 +97,7 @@ impl Model {
         while let Some(node) = self.queue.pop_front() {
             if node == self.right_clicked {
                 self.queue.clear();
-                let mut path = vec![node];
+                let mut path = Vec::new();
                 let mut current = node;
                 while let Some(&predecessor) = self.predecessor.get(&current) {
                     path.push(current);
@@ -80,7 +115,7 @@ impl Model {
                 continue;
             }
             self.visited.insert(node);
-            for neighbor in &self.graph[&node] {
+            for neighbor in self.graph.neighbors(node) {
                 if !self.visited.contains(neighbor) {
                     self.queue.push_back(*neighbor);
                     self.predecessor.insert(*neighbor, node);
@@ -91,34 +126,141 @@ impl Model {
         }
         self.state = ModelState::Idle;
     }
-}
 
-fn update(app: &App, model: &mut Model, _update: Update) {
-    if app.mouse.buttons.left().is_down() {
-        if let Some(closest) = model.positions.iter().min_by_key(|(_, pos)| pos.distance(app.mouse.position()).round() as u32) {
-            model.left_clicked = *closest.0;
-            model.visited.clear();
-            model.shortest_path.clear();
-            model.queue.clear();
-            model.queue.push_back(model.left_clicked);
-            model.predecessor.clear();
-            model.state = ModelState::CalculatingShortestPath;
+    fn restart_search(&mut self) {
+        self.visited.clear();
+        self.shortest_path.clear();
+        self.queue.clear();
+        self.queue.push_back(self.left_clicked);
+        self.predecessor.clear();
+        self.state = ModelState::CalculatingShortestPath;
+    }
+
+    // Restarts the search whenever a chunk grows the graph, a path may exist now.
+    fn load_step(&mut self) {
+        let Some(loader) = &mut self.loader else {
+            return;
+        };
+        let size = (self.graph.node_count(), self.graph.edge_count());
+        match loader.poll(&mut self.graph) {
+            LoadStatus::Loading => {}
+            LoadStatus::Done => self.loader = None,
+            LoadStatus::Failed(err) => {
+                self.loader = None;
+                self.load_error = Some(err);
+            }
         }
+        if size != (self.graph.node_count(), self.graph.edge_count()) {
+            self.restart_search();
+        }
+    }
+
+    // Maps graph coordinates into the window, so imported graphs of any extent fit on screen.
+    // Axes without extent (a single node, or all nodes on one line) don't constrain the scale.
+    fn screen_transform(&self) -> ScreenTransform {
+        let Some(bounds) = self.graph.bounds() else {
+            return ScreenTransform { center: Point2::ZERO, scale: 1.0 };
+        };
+        let scale_x = (bounds.w() > 0.0).then(|| FIT * WIDTH as f32 / bounds.w());
+        let scale_y = (bounds.h() > 0.0).then(|| FIT * HEIGHT as f32 / bounds.h());
+        let scale = match (scale_x, scale_y) {
+            (Some(x), Some(y)) => x.min(y),
+            (Some(scale), None) | (None, Some(scale)) => scale,
+            (None, None) => 1.0,
+        };
+        ScreenTransform { center: bounds.xy(), scale }
+    }
+
+    fn screen_position(&self, transform: ScreenTransform, node: u32) -> Point2 {
+        transform.apply(self.graph.position(node))
+    }
+
+    fn closest_node(&self, pos: Point2) -> Option<u32> {
+        let transform = self.screen_transform();
+        self.graph.nodes().min_by_key(|&node| self.screen_position(transform, node).distance(pos).round() as u32)
+    }
+
+    // Selects the start (left button) or goal (right button) node closest to the
+    // window position and restarts the search.
+    fn click(&mut self, button: MouseButton, pos: Point2) {
+        let Some(closest) = self.closest_node(pos) else {
+            return;
+        };
+        match button {
+            MouseButton::Left => self.left_clicked = closest,
+            MouseButton::Right => self.right_clicked = closest,
+            _ => return,
+        }
+        self.restart_search();
     }
-    if app.mouse.buttons.right().is_down() {
-        if let Some(closest) = model.positions.iter().min_by_key(|(_, pos)| pos.distance(app.mouse.position()).round() as u32) {
-            model.right_clicked = *closest.0;
-            model.visited.clear();
-            model.shortest_path.clear();
-            model.queue.clear();
-            model.queue.push_back(model.left_clicked);
-            model.predecessor.clear();
-            model.state = ModelState::CalculatingShortestPath;
+
+    // Applies the mouse state of one frame, buttons are handled while held down.
+    pub fn handle_input(&mut self, left_down: bool, right_down: bool, mouse_pos: Point2) {
+        if left_down {
+            self.click(MouseButton::Left, mouse_pos);
+        }
+        if right_down {
+            self.click(MouseButton::Right, mouse_pos);
         }
     }
-    if let ModelState::CalculatingShortestPath = model.state {
-        model.shortest_path_step();
+
+    pub fn key_pressed(&mut self, key: Key) {
+        match key {
+            Key::H => self.show_help = !self.show_help,
+            Key::L => self.language = self.language.next(),
+            _ => {}
+        }
     }
+
+    // Advances loading and the search by one frame, independent of the nannou app.
+    pub fn step(&mut self) {
+        self.load_step();
+        if let ModelState::CalculatingShortestPath = self.state {
+            self.shortest_path_step();
+        }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loader.is_some()
+    }
+
+    pub fn load_error(&self) -> Option<&LoadError> {
+        self.load_error.as_ref()
+    }
+
+    pub fn left_clicked(&self) -> u32 {
+        self.left_clicked
+    }
+
+    pub fn right_clicked(&self) -> u32 {
+        self.right_clicked
+    }
+
+    pub fn shortest_path(&self) -> &[u32] {
+        &self.shortest_path
+    }
+
+    pub fn state(&self) -> ModelState {
+        self.state
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn show_help(&self) -> bool {
+        self.show_help
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    let buttons = &app.mouse.buttons;
+    model.handle_input(buttons.left().is_down(), buttons.right().is_down(), app.mouse.position());
+    model.step();
 }
 
 fn view(app: &App, model: &Model, frame: Frame) {
@@ -128,14 +270,91 @@ fn view(app: &App, model: &Model, frame: Frame) {
 
     draw_model(&draw, model);
     draw_mouse_lines(app, &draw, model);
+    draw_hud(app, &draw, model);
 
     // Write the result of our drawing to the window's frame.
     draw.to_frame(app, &frame).unwrap();
 }
 
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    model.key_pressed(key);
+}
+
+fn draw_hud(app: &App, draw: &Draw, model: &Model) {
+    let tr = |text| model.language.tr(text);
+    let top_left = app.window_rect().top_left() + vec2(10.0, -10.0);
+    // Labels never wrap, longer strings run past the box instead of overlapping the next line.
+    let label = |text: &str, color: Srgb<u8>, line: f32| {
+        draw.text(text)
+            .no_line_wrap()
+            .left_justify()
+            .align_text_top()
+            .font_size(14)
+            .color(color)
+            .x_y(top_left.x + 120.0, top_left.y - 10.0 - 18.0 * line)
+            .w_h(200.0, 18.0)
+            .z(5.0);
+    };
+
+    let status = match (&model.loader, &model.load_error, &model.state) {
+        (_, Some(err), _) => err.message(model.language),
+        (Some(loader), _, _) => format!("{} {:.0}%", tr(Text::StateLoading), loader.progress() * 100.0),
+        (_, _, ModelState::CalculatingShortestPath) => tr(Text::StateCalculating).to_string(),
+        (_, _, ModelState::Idle) if model.left_clicked == model.right_clicked => tr(Text::StateIdle).to_string(),
+        (_, _, ModelState::Idle) if model.shortest_path.is_empty() => tr(Text::ErrorNoPath).to_string(),
+        (_, _, ModelState::Idle) => tr(Text::StateIdle).to_string(),
+    };
+    label(&status, WHITE, 0.0);
+
+    // Nodes are shown as a dot, edges as a line sample, matching `draw_model`.
+    let legend = [
+        (tr(Text::LegendStart), RED, false),
+        (tr(Text::LegendGoal), BLUE, false),
+        (tr(Text::LegendVisited), RED, true),
+        (tr(Text::LegendPath), TEAL, true),
+    ];
+    for (line, (text, color, edge)) in legend.into_iter().enumerate() {
+        let line = line as f32 + 1.5;
+        let swatch = pt2(top_left.x + 5.0, top_left.y - 10.0 - 18.0 * line);
+        if edge {
+            draw.line()
+                .start(swatch - vec2(8.0, 0.0))
+                .end(swatch + vec2(8.0, 0.0))
+                .color(color)
+                .stroke_weight(2.0)
+                .z(5.0);
+        } else {
+            draw.ellipse()
+                .xy(swatch)
+                .w_h(10.0, 10.0)
+                .color(color)
+                .z(5.0);
+        }
+        label(text, color, line);
+    }
+
+    if !model.show_help {
+        label(tr(Text::HelpHint), LIGHTGRAY, 6.0);
+        return;
+    }
+    let help = [
+        Text::HelpTitle,
+        Text::HelpLeftClick,
+        Text::HelpRightClick,
+        Text::HelpToggleHelp,
+        Text::HelpSwitchLanguage,
+    ];
+    for (line, text) in help.into_iter().enumerate() {
+        label(tr(text), LIGHTGRAY, line as f32 + 6.0);
+    }
+    let language = format!("{} {}", tr(Text::HelpLanguage), model.language.name());
+    label(&language, LIGHTGRAY, help.len() as f32 + 6.0);
+}
+
 fn draw_mouse_lines(app: &App, draw: &Draw, model: &Model) {
     let mouse_pos = app.mouse.position();
-    for pos in model.positions.values().into_iter().filter(|next_pos| next_pos.distance(mouse_pos) < 200.0) {
+    let transform = model.screen_transform();
+    for pos in model.graph.nodes().map(|node| model.screen_position(transform, node)).filter(|next_pos| next_pos.distance(mouse_pos) < 200.0) {
         let color = LinSrgba::new(0.0, 0.0, 0.0, 1.0 - (200.0 / pos.distance(mouse_pos)));
         draw.line()
             .start(pt2(pos.x, pos.y))
@@ -145,11 +364,17 @@ fn draw_mouse_lines(app: &App, draw: &Draw, model: &Model) {
 }
 
 fn draw_model(draw: &Draw, model: &Model) {
-    for (node, neighbors) in &model.graph {
-        let pos = model.positions[node];
+    // Looked up once per edge, so large graphs don't scan the whole path for every edge.
+    let path_edges: HashSet<(u32, u32)> = model.shortest_path
+        .windows(2)
+        .flat_map(|x| [(x[0], x[1]), (x[1], x[0])])
+        .collect();
+    let transform = model.screen_transform();
+    for node in model.graph.nodes() {
+        let pos = model.screen_position(transform, node);
         let (color, z_index) = match node {
-            _ if node == &model.left_clicked => (RED, 4.0),
-            _ if node == &model.right_clicked => (BLUE, 4.0),
+            _ if node == model.left_clicked => (RED, 4.0),
+            _ if node == model.right_clicked => (BLUE, 4.0),
             _ => (WHITE, 3.0),
         };
         draw.ellipse()
@@ -157,15 +382,15 @@ fn draw_model(draw: &Draw, model: &Model) {
             .color(color)
             .z(z_index);
         
-        for j in neighbors {
-            let (color, line_width) = if model.shortest_path.windows(2).find(|x| x[0] == *node && x[1] == *j || x[1] == *node && x[0] == *j).is_some() {
+        for &j in model.graph.neighbors(node) {
+            let (color, line_width) = if path_edges.contains(&(node, j)) {
                 (TEAL, 2.0)
-            } else if model.visited.contains(node) {
+            } else if model.visited.contains(&node) {
                 (RED, 1.0)
             } else {
                 (WHITE, 1.0)
             };
-            let neighbor_pos = model.positions[j];
+            let neighbor_pos = model.screen_position(transform, j);
             draw.line()
                 .start(pt2(pos.x, pos.y))
                 .end(pt2(neighbor_pos.x, neighbor_pos.y))
@@ -176,8 +401,11 @@ fn draw_model(draw: &Draw, model: &Model) {
     }
 }
 
-pub async fn run_app() {
-    let model = Model::new_random(250);
+pub async fn run_app(path: Option<String>) {
+    let model = match path {
+        Some(path) => Model::load(&path),
+        None => Model::new_random(250),
+    };
     thread_local!(static MODEL: RefCell<Option<Model>> = Default::default());    
     MODEL.with(|m| m.borrow_mut().replace(model));
 
@@ -207,7 +435,7 @@ async fn create_window(app: &App) {
         .title("nannou web test")
         .size(WIDTH, HEIGHT)
         // .raw_event(raw_event)
-        // .key_pressed(key_pressed)
+        .key_pressed(key_pressed)
         // .key_released(key_released)
         // .mouse_pressed(mouse_pressed)
         // .mouse_moved(mouse_moved)
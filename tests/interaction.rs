@@ -0,0 +1,179 @@
+// Drives the model headlessly through the same entry points the nannou callbacks use
+// (`handle_input`, `key_pressed`, `step`), so no window or GPU is needed.
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nannou::prelude::*;
+use nannou_web_test::graph::Graph;
+use nannou_web_test::i18n::Language;
+use nannou_web_test::loader::LoadError;
+use nannou_web_test::sketch::{Model, ModelState};
+
+struct Harness {
+    model: Model,
+}
+
+impl Harness {
+    // 0 - 1 - 2 on the diagonal, 3 and 4 are isolated corners. The nodes span the
+    // whole window, so they are drawn at 0.9 times their graph coordinates.
+    fn line_graph() -> Self {
+        let mut graph = Graph::new();
+        for (x, y) in [(-500.0, -500.0), (0.0, 0.0), (500.0, 500.0), (500.0, -500.0), (-500.0, 500.0)] {
+            graph.push_node(pt2(x, y));
+        }
+        for (from, to) in [(0, 1), (1, 0), (1, 2), (2, 1)] {
+            graph.push_edge(from, to);
+        }
+        Harness { model: Model::from_graph(graph) }
+    }
+
+    fn load(contents: &str, name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("nannou_pathfinding_{}_{}.txt", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        let mut harness = Harness { model: Model::load(path.to_str().unwrap()) };
+        harness.load_until_done();
+        fs::remove_file(&path).unwrap();
+        harness
+    }
+
+    // One frame with the given button held down, followed by a frame with it released.
+    fn click(&mut self, button: MouseButton, x: f32, y: f32) {
+        let pos = pt2(x, y);
+        self.model.handle_input(button == MouseButton::Left, button == MouseButton::Right, pos);
+        self.model.handle_input(false, false, pos);
+    }
+
+    fn press(&mut self, key: Key) {
+        self.model.key_pressed(key);
+    }
+
+    fn run_until_idle(&mut self) {
+        for _ in 0..1000 {
+            self.model.step();
+            if self.model.state() == ModelState::Idle {
+                return;
+            }
+        }
+        panic!("search did not finish");
+    }
+
+    fn load_until_done(&mut self) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            self.model.step();
+            if !self.model.is_loading() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        panic!("loading did not finish");
+    }
+}
+
+#[test]
+fn finds_initial_path() {
+    let mut harness = Harness::line_graph();
+    assert_eq!(harness.model.state(), ModelState::CalculatingShortestPath);
+    harness.run_until_idle();
+    assert_eq!(harness.model.shortest_path(), &[0, 1]);
+}
+
+#[test]
+fn clicks_select_closest_nodes() {
+    let mut harness = Harness::line_graph();
+    harness.click(MouseButton::Left, 480.0, -470.0);
+    assert_eq!(harness.model.left_clicked(), 3);
+    harness.click(MouseButton::Right, -10.0, 20.0);
+    assert_eq!(harness.model.right_clicked(), 1);
+    harness.click(MouseButton::Middle, 500.0, 500.0);
+    assert_eq!(harness.model.left_clicked(), 3);
+    assert_eq!(harness.model.right_clicked(), 1);
+}
+
+#[test]
+fn both_buttons_select_start_and_goal() {
+    let mut harness = Harness::line_graph();
+    harness.model.handle_input(true, true, pt2(490.0, 490.0));
+    assert_eq!(harness.model.left_clicked(), 2);
+    assert_eq!(harness.model.right_clicked(), 2);
+}
+
+#[test]
+fn click_restarts_search() {
+    let mut harness = Harness::line_graph();
+    harness.run_until_idle();
+    harness.click(MouseButton::Right, 490.0, 490.0);
+    assert_eq!(harness.model.state(), ModelState::CalculatingShortestPath);
+    assert!(harness.model.shortest_path().is_empty());
+    harness.run_until_idle();
+    assert_eq!(harness.model.shortest_path(), &[0, 1, 2]);
+}
+
+#[test]
+fn unreachable_goal_has_no_path() {
+    let mut harness = Harness::line_graph();
+    harness.click(MouseButton::Right, -500.0, 500.0);
+    harness.run_until_idle();
+    assert_eq!(harness.model.right_clicked(), 4);
+    assert!(harness.model.shortest_path().is_empty());
+}
+
+#[test]
+fn keys_toggle_help_and_language() {
+    let mut harness = Harness::line_graph();
+    assert!(!harness.model.show_help());
+    harness.press(Key::H);
+    assert!(harness.model.show_help());
+    harness.press(Key::L);
+    assert_eq!(harness.model.language(), Language::German);
+    harness.press(Key::L);
+    assert_eq!(harness.model.language(), Language::English);
+    harness.press(Key::Q);
+    assert!(harness.model.show_help());
+}
+
+#[test]
+fn loads_graph_file() {
+    let mut harness = Harness::load("# line\nv 0 0\nv 1 1\nv 2 2\ne 0 1\ne 1 0\ne 1 2\ne 2 1\n", "valid");
+    assert!(harness.model.load_error().is_none());
+    assert_eq!(harness.model.graph().node_count(), 3);
+    assert_eq!(harness.model.graph().neighbors(1), &[0, 2]);
+    harness.click(MouseButton::Right, 500.0, 500.0);
+    harness.run_until_idle();
+    assert_eq!(harness.model.shortest_path(), &[0, 1, 2]);
+}
+
+#[test]
+fn finds_path_after_loading() {
+    let mut harness = Harness::load("v 0 0\nv 1 1\nv 2 2\ne 0 2\ne 2 1\n", "initial");
+    harness.run_until_idle();
+    assert_eq!(harness.model.shortest_path(), &[0, 2, 1]);
+}
+
+#[test]
+fn scales_graph_on_a_single_line() {
+    let mut harness = Harness::load("v 0 7000\nv 10000 7000\nv 20000 7000\n", "line");
+    harness.click(MouseButton::Left, 480.0, 0.0);
+    assert_eq!(harness.model.left_clicked(), 2);
+    harness.click(MouseButton::Left, 20.0, 30.0);
+    assert_eq!(harness.model.left_clicked(), 1);
+}
+
+#[test]
+fn reports_unsorted_edges() {
+    let harness = Harness::load("v 0 0\nv 1 1\ne 1 0\ne 0 1\n", "unsorted");
+    assert!(matches!(harness.model.load_error(), Some(LoadError::UnsortedEdges { line: 4 })));
+    assert_eq!(harness.model.graph().node_count(), 2);
+    assert_eq!(harness.model.graph().neighbors(1), &[0]);
+}
+
+#[test]
+fn rejects_non_finite_coordinates() {
+    for (coordinate, name) in [("NaN", "nan"), ("inf", "inf"), ("-inf", "neg_inf")] {
+        let harness = Harness::load(&format!("v 0 0\nv {} 1\n", coordinate), name);
+        assert!(matches!(harness.model.load_error(), Some(LoadError::Parse { line: 2 })));
+        assert_eq!(harness.model.graph().node_count(), 1);
+    }
+}